@@ -4,6 +4,12 @@
 //! - User-selectable password length
 //! - User-selectable number of passwords
 //! - User-selectable retry bound for dead-end handling
+//! - Optional deterministic seed for reproducible output
+//! - Optional literal prefix/suffix constraints
+//! - Optional exclusion of visually ambiguous characters
+//! - Optional dropping of individual character classes
+//! - Optional minimum-entropy target mode, growing length to fit
+//! - Batch generation with cross-password uniqueness, optionally parallel
 //!
 //! Exit codes:
 //! 0 = success
@@ -12,17 +18,40 @@
 
 mod password;
 
-use password::Generator;
+use password::{Generator, SplitMix64};
+use rand_core::OsRng;
 use std::env;
 use std::num::NonZeroUsize;
 use std::process::ExitCode;
 
+/// Starting length for `--min-entropy` mode's length search.
+const MIN_ENTROPY_START_LENGTH: usize = 16;
+
+/// Upper bound on `--min-entropy` mode's length search, guarding against an
+/// unreachable target (e.g. every class disabled) looping forever.
+const MIN_ENTROPY_MAX_LENGTH: usize = 4096;
+
 /// Parsed command-line configuration.
 #[derive(Debug)]
 struct Config {
-    length: NonZeroUsize,
+    length: Option<NonZeroUsize>,
     count: NonZeroUsize,
     max_retries: NonZeroUsize,
+    seed: Option<u64>,
+    prefix: String,
+    suffix: String,
+    exclude: String,
+    uppercase: Option<String>,
+    lowercase: Option<String>,
+    digits: Option<String>,
+    special: Option<String>,
+    without_uppercase: bool,
+    without_lowercase: bool,
+    without_digits: bool,
+    without_special: bool,
+    min_entropy: Option<f64>,
+    show_entropy: bool,
+    parallel: bool,
 }
 
 impl Config {
@@ -30,6 +59,21 @@ impl Config {
         let mut length: Option<NonZeroUsize> = None;
         let mut count: Option<NonZeroUsize> = None;
         let mut retries: Option<NonZeroUsize> = None;
+        let mut seed: Option<u64> = None;
+        let mut prefix = String::new();
+        let mut suffix = String::new();
+        let mut exclude = String::new();
+        let mut uppercase: Option<String> = None;
+        let mut lowercase: Option<String> = None;
+        let mut digits: Option<String> = None;
+        let mut special: Option<String> = None;
+        let mut without_uppercase = false;
+        let mut without_lowercase = false;
+        let mut without_digits = false;
+        let mut without_special = false;
+        let mut min_entropy: Option<f64> = None;
+        let mut show_entropy = false;
+        let mut parallel = false;
 
         let mut args = env::args().skip(1);
 
@@ -44,6 +88,51 @@ impl Config {
                 "--max-retries" => {
                     retries = Some(parse_nz(args.next(), "max-retries")?);
                 }
+                "--seed" => {
+                    seed = Some(parse_seed(args.next())?);
+                }
+                "--prefix" => {
+                    prefix = args.next().ok_or("Missing value for prefix")?;
+                }
+                "--suffix" => {
+                    suffix = args.next().ok_or("Missing value for suffix")?;
+                }
+                "--exclude" => {
+                    exclude = args.next().ok_or("Missing value for exclude")?;
+                }
+                "--uppercase" => {
+                    uppercase = Some(args.next().ok_or("Missing value for uppercase")?);
+                }
+                "--lowercase" => {
+                    lowercase = Some(args.next().ok_or("Missing value for lowercase")?);
+                }
+                "--digits" => {
+                    digits = Some(args.next().ok_or("Missing value for digits")?);
+                }
+                "--special" => {
+                    special = Some(args.next().ok_or("Missing value for special")?);
+                }
+                "--without-uppercase" => {
+                    without_uppercase = true;
+                }
+                "--without-lowercase" => {
+                    without_lowercase = true;
+                }
+                "--without-digits" => {
+                    without_digits = true;
+                }
+                "--without-special" => {
+                    without_special = true;
+                }
+                "--min-entropy" => {
+                    min_entropy = Some(parse_f64(args.next(), "min-entropy")?);
+                }
+                "--show-entropy" => {
+                    show_entropy = true;
+                }
+                "--parallel" => {
+                    parallel = true;
+                }
                 "-h" | "--help" => {
                     print_help();
                     std::process::exit(0);
@@ -54,18 +143,39 @@ impl Config {
             }
         }
 
-        let length = length.ok_or("Missing required option: --length")?;
+        if min_entropy.is_none() {
+            let length = length.ok_or("Missing required option: --length")?;
+            if length.get() < 16 {
+                return Err("Password length must be >= 16".into());
+            }
+        } else if let Some(length) = length {
+            if length.get() < 16 {
+                return Err("Password length must be >= 16".into());
+            }
+        }
+
         let count = count.unwrap_or_else(|| nz(1));
         let max_retries = retries.unwrap_or_else(|| nz(256));
 
-        if length.get() < 16 {
-            return Err("Password length must be >= 16".into());
-        }
-
         Ok(Self {
             length,
             count,
             max_retries,
+            seed,
+            prefix,
+            suffix,
+            exclude,
+            uppercase,
+            lowercase,
+            digits,
+            special,
+            without_uppercase,
+            without_lowercase,
+            without_digits,
+            without_special,
+            min_entropy,
+            show_entropy,
+            parallel,
         })
     }
 }
@@ -80,11 +190,90 @@ fn main() -> ExitCode {
         }
     };
 
-    let generator = Generator::new(config.max_retries.get());
+    let mut builder = Generator::builder().max_attempts(config.max_retries.get());
+    if let Some(set) = &config.uppercase {
+        builder = builder.uppercase(set.clone().into_bytes());
+    }
+    if let Some(set) = &config.lowercase {
+        builder = builder.lowercase(set.clone().into_bytes());
+    }
+    if let Some(set) = &config.digits {
+        builder = builder.digits(set.clone().into_bytes());
+    }
+    if let Some(set) = &config.special {
+        builder = builder.special(set.clone().into_bytes());
+    }
+    if config.without_uppercase {
+        builder = builder.without_uppercase();
+    }
+    if config.without_lowercase {
+        builder = builder.without_lowercase();
+    }
+    if config.without_digits {
+        builder = builder.without_digits();
+    }
+    if config.without_special {
+        builder = builder.without_special();
+    }
+    // Applied last so --exclude also strips from any custom set passed via
+    // --uppercase/--lowercase/--digits/--special, not just the defaults.
+    if !config.exclude.is_empty() {
+        builder = builder.exclude(config.exclude.as_bytes());
+    }
+    let generator = match builder.build() {
+        Ok(g) => g,
+        Err(err) => {
+            eprintln!("Invalid character set: {err:?}");
+            return ExitCode::from(2);
+        }
+    };
+    // The plain case (no seed, no growing-length search) can use the
+    // batch API, which amortizes affix validation and SIMD backend
+    // detection across the whole `--count` run and can fan out across
+    // threads with `--parallel`.
+    if config.seed.is_none() && config.min_entropy.is_none() {
+        let length = config.length.expect("validated in Config::parse").get();
+        let count = config.count.get();
+
+        let result = if config.parallel {
+            generator.generate_batch_parallel(length, count, &config.prefix, &config.suffix)
+        } else {
+            generator.generate_batch_with_affix_and_rng(
+                length,
+                count,
+                &config.prefix,
+                &config.suffix,
+                &mut OsRng,
+            )
+        };
+
+        return match result {
+            Ok(passwords) => {
+                print_passwords(&generator, &config, passwords);
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Generation failed: {err:?}");
+                ExitCode::from(2)
+            }
+        };
+    }
+
+    let mut seeded_rng = config.seed.map(SplitMix64::from_seed);
 
     for _ in 0..config.count.get() {
-        match generator.generate(config.length.get()) {
-            Ok(pw) => println!("{pw}"),
+        let result = match config.min_entropy {
+            Some(target) => generate_meeting_entropy(&generator, &config, target, &mut seeded_rng),
+            None => generate_one(
+                &generator,
+                &config,
+                config.length.expect("validated in Config::parse").get(),
+                &mut seeded_rng,
+            ),
+        };
+
+        match result {
+            Ok(pw) => print_passwords(&generator, &config, vec![pw]),
             Err(err) => {
                 eprintln!("Generation failed: {err:?}");
                 return ExitCode::from(2);
@@ -95,6 +284,59 @@ fn main() -> ExitCode {
     ExitCode::SUCCESS
 }
 
+/// Print each password, appending its estimated bit-strength when
+/// `--show-entropy` was given.
+fn print_passwords(generator: &Generator, config: &Config, passwords: Vec<String>) {
+    for pw in passwords {
+        if config.show_entropy {
+            println!("{pw}  ({:.2} bits)", generator.entropy_bits(&pw));
+        } else {
+            println!("{pw}");
+        }
+    }
+}
+
+/// Generate a single password at `length`, dispatching to the seeded RNG
+/// when `--seed` was given and to `OsRng` otherwise.
+fn generate_one(
+    generator: &Generator,
+    config: &Config,
+    length: usize,
+    seeded_rng: &mut Option<SplitMix64>,
+) -> Result<String, password::GeneratorError> {
+    match seeded_rng.as_mut() {
+        Some(rng) => generator.generate_with_affix_and_rng(length, &config.prefix, &config.suffix, rng),
+        None => {
+            generator.generate_with_affix_and_rng(length, &config.prefix, &config.suffix, &mut OsRng)
+        }
+    }
+}
+
+/// `--min-entropy` mode: grow `length` from [`MIN_ENTROPY_START_LENGTH`] (or
+/// the user's `--length`, if given) until the generated password's estimated
+/// bit-strength meets `target`.
+fn generate_meeting_entropy(
+    generator: &Generator,
+    config: &Config,
+    target: f64,
+    seeded_rng: &mut Option<SplitMix64>,
+) -> Result<String, password::GeneratorError> {
+    let mut length = config
+        .length
+        .map(NonZeroUsize::get)
+        .unwrap_or(MIN_ENTROPY_START_LENGTH);
+
+    loop {
+        let pw = generate_one(generator, config, length, seeded_rng)?;
+
+        if generator.entropy_bits(&pw) >= target || length >= MIN_ENTROPY_MAX_LENGTH {
+            return Ok(pw);
+        }
+
+        length += 1;
+    }
+}
+
 /// Parse a required positive integer argument.
 fn parse_nz(value: Option<String>, name: &str) -> Result<NonZeroUsize, String> {
     let raw = value.ok_or_else(|| format!("Missing value for {name}"))?;
@@ -109,6 +351,20 @@ fn nz(v: usize) -> NonZeroUsize {
     NonZeroUsize::new(v).expect("non-zero constant")
 }
 
+/// Parse a `--seed` argument as a hex-encoded u64.
+fn parse_seed(value: Option<String>) -> Result<u64, String> {
+    let raw = value.ok_or("Missing value for seed")?;
+    let trimmed = raw.strip_prefix("0x").unwrap_or(&raw);
+    u64::from_str_radix(trimmed, 16).map_err(|_| format!("Invalid hex value for seed: {raw}"))
+}
+
+/// Parse a required floating-point argument.
+fn parse_f64(value: Option<String>, name: &str) -> Result<f64, String> {
+    let raw = value.ok_or_else(|| format!("Missing value for {name}"))?;
+    raw.parse()
+        .map_err(|_| format!("Invalid numeric value for {name}: {raw}"))
+}
+
 fn print_help() {
     println!(
         "\
@@ -116,13 +372,29 @@ Advanced Password Generator
 
 USAGE:
     adv-pwd-gen --length <N> [OPTIONS]
+    adv-pwd-gen --min-entropy <BITS> [OPTIONS]
 
 REQUIRED:
-    -l, --length <N>        Password length (>= 16)
+    -l, --length <N>        Password length (>= 16); omit if --min-entropy is given
 
 OPTIONS:
     -n, --count <N>         Number of passwords to generate (default: 1)
         --max-retries <N>   Retry bound for dead-end recovery (default: 256)
+        --seed <HEX>        Deterministic seed for reproducible output (audits/tests)
+        --prefix <STR>      Pin literal characters at the start of the password
+        --suffix <STR>      Pin literal characters at the end of the password
+        --exclude <CHARS>   Strip these characters from all classes (e.g. O0l1I|)
+        --uppercase <SET>   Replace the uppercase class's character set
+        --lowercase <SET>   Replace the lowercase class's character set
+        --digits <SET>      Replace the digits class's character set
+        --special <SET>     Replace the special-character class's character set
+        --without-uppercase Drop the uppercase class entirely
+        --without-lowercase Drop the lowercase class entirely
+        --without-digits    Drop the digits class entirely
+        --without-special   Drop the special-character class entirely
+        --min-entropy <BITS> Grow length (from 16) until this bit-strength is met
+        --show-entropy      Print each password's estimated bit-strength
+        --parallel          Fan a large --count batch across threads
     -h, --help              Show this help message
 "
     );